@@ -0,0 +1,5 @@
+//! Library half of `metrum-cli`: the Metrum calendar/time math, kept usable outside the TUI
+//! binary (embedded, WASM, ...). See `time` for details.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod time;
@@ -1,10 +1,22 @@
-mod time;
+// The UI only ever reads the clock through `MetrumDateTime::now`/`now_local`, both of which are
+// `chrono`-gated in `time.rs`; without it there's nothing for this binary to display.
+#[cfg(feature = "chrono")]
 mod ui;
 
+#[cfg(feature = "chrono")]
 use std::{sync::{Arc, Mutex}, thread, time::Duration};
+#[cfg(feature = "chrono")]
 use ui::Cli;
+#[cfg(feature = "chrono")]
 use crossterm::{event::{Event, KeyCode, KeyEvent}, terminal::{Clear, ClearType}};
 
+#[cfg(not(feature = "chrono"))]
+fn main() {
+    eprintln!("metrum-cli requires the `chrono` feature (on by default); rebuild without --no-default-features.");
+    std::process::exit(1);
+}
+
+#[cfg(feature = "chrono")]
 fn main() {
     print!("{}", Clear(ClearType::All));
     let mut cli = Cli::new();
@@ -27,9 +39,22 @@ fn main() {
         cli.render();
         thread::sleep(Duration::from_millis(20));
         for key in keys.lock().unwrap().drain(..) {
-            match key {
-                KeyEvent { code: KeyCode::Char('q'), ..} => break 'main_loop,
-                _ => ()
+            if cli.is_input_active() {
+                match key {
+                    KeyEvent { code: KeyCode::Enter, ..} => { let _ = cli.confirm_input(); },
+                    KeyEvent { code: KeyCode::Esc, ..} => cli.cancel_input(),
+                    KeyEvent { code: KeyCode::Backspace, ..} => cli.backspace_input(),
+                    KeyEvent { code: KeyCode::Char(c), ..} => cli.push_input_char(c),
+                    _ => ()
+                }
+            } else {
+                match key {
+                    KeyEvent { code: KeyCode::Char('q'), ..} => break 'main_loop,
+                    KeyEvent { code: KeyCode::Char('z'), ..} => cli.cycle_timezone(),
+                    KeyEvent { code: KeyCode::Char('t'), ..} => cli.begin_input(),
+                    KeyEvent { code: KeyCode::Char('r'), ..} => cli.resume(),
+                    _ => ()
+                }
             }
         }
     }
@@ -1,5 +1,22 @@
-use chrono::{Datelike, NaiveDateTime, Timelike, Utc};
-use num_traits::cast::FromPrimitive;
+//! Core Metrum date/time types and conversions.
+//!
+//! The conversion and arithmetic logic in this module only touches `core`, so it compiles under
+//! `#![no_std]` (see `lib.rs`), as the `gregor` crate does for its calendar math. Chrono only
+//! enters through [`MetrumDateTime::from_naive`]/[`MetrumDateTime::now`] and
+//! [`MetrumDateTime::from_offset`]/[`MetrumDateTime::now_local`], gated behind the
+//! default `chrono` feature; everywhere else, feed in a clock with
+//! [`MetrumDateTime::from_unix_timestamp`].
+
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, Local, NaiveDateTime, Offset, Timelike, Utc};
+
+#[cfg(feature = "std")]
+pub mod format;
+pub mod duration;
+// Like `format`, this module's representations (`String`, `format!`) need an allocator, so it
+// rides along with the `std` feature rather than `serde` alone.
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod serde;
 
 #[derive(Debug)]
 pub enum TimeError {
@@ -13,7 +30,9 @@ pub enum TimeError {
     InvalidUtcHour,
     InvalidUtcMinute,
     InvalidUtcSecond,
-    InvalidUtcNano
+    InvalidUtcNano,
+
+    ParseMismatch
 }
 
 pub const SUBTICKS_PER_TICK: u32 = 1_000_000;
@@ -28,7 +47,10 @@ pub const DAYS_PER_LEAP_YEAR: u16 = 366;
 pub const MILLIS_PER_TICK: u16 = 864;
 pub const MICROS_PER_TICK: u32 = 864_000;
 
+const YEAR_OFFSET: i32 = 2000;
+
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct MetrumDate {
     year: i32,
     day: u16,
@@ -47,23 +69,32 @@ impl MetrumDate {
         if month == 0 || month > 12 {
             return Err(TimeError::InvalidUtcMonth);
         }
-        let chrono_month = chrono::Month::from_u32(month as u32).unwrap();
-        if day > days_in_month(chrono_month, year) {
+        if day > days_in_month(month, year) {
             return Err(TimeError::InvalidUtcDay);
         }
         Ok(Self {
             year,
-            day: year_day(year, chrono_month, day)
+            day: year_day(year, month, day)
         })
     }
 }
-impl std::fmt::Display for MetrumDate {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MetrumDate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}'{}", self.year, self.day)
     }
 }
+impl core::str::FromStr for MetrumDate {
+    type Err = TimeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, day) = s.split_once('\'').ok_or(TimeError::ParseMismatch)?;
+        let year: i32 = year.parse().map_err(|_| TimeError::ParseMismatch)?;
+        let day: u16 = day.parse().map_err(|_| TimeError::ParseMismatch)?;
+        Self::new(year, day)
+    }
+}
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct MetrumTime {
     minute: u16,
     tick: u8,
@@ -115,13 +146,25 @@ impl MetrumTime {
     pub fn subtick(&self) -> u32 { self.subtick }
     
 }
-impl std::fmt::Display for MetrumTime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MetrumTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:0>3}:{:0>2}.{:0>6}", self.minute, self.tick, self.subtick)
     }
 }
+impl core::str::FromStr for MetrumTime {
+    type Err = TimeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (minute, rest) = s.split_once(':').ok_or(TimeError::ParseMismatch)?;
+        let (tick, subtick) = rest.split_once('.').ok_or(TimeError::ParseMismatch)?;
+        let minute: u16 = minute.parse().map_err(|_| TimeError::ParseMismatch)?;
+        let tick: u8 = tick.parse().map_err(|_| TimeError::ParseMismatch)?;
+        let subtick: u32 = subtick.parse().map_err(|_| TimeError::ParseMismatch)?;
+        Self::new(minute, tick, subtick)
+    }
+}
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct MetrumDateTime {
     date: MetrumDate,
     time: MetrumTime
@@ -135,49 +178,16 @@ impl MetrumDateTime {
     }
 
     pub fn from_timestamp(timestamp: i64) -> Self {
-        let mut year: i32 = 2000;
-        let mut timestamp_remaining = timestamp;
-
-        let mut next_year_ticks = if is_leap_year(year + if timestamp_remaining < 0 {-1} else {0}) {366 * TICKS_PER_DAY as i64} else {365 * TICKS_PER_DAY as i64};
-
-        while timestamp_remaining.abs() >= next_year_ticks {
-            if timestamp_remaining < 0 {
-                timestamp_remaining += next_year_ticks;
-                year -= 1;
-            } else {
-                timestamp_remaining -= next_year_ticks;
-                year += 1;
-            }
-            next_year_ticks = if is_leap_year(year + if timestamp_remaining < 0 {-1} else {0}) {366 * TICKS_PER_DAY as i64} else {365 * TICKS_PER_DAY as i64};
-        }
-        if timestamp_remaining >= 0 {
-            let day = (timestamp_remaining / TICKS_PER_DAY as i64) as u16;
-            let day_ticks = (timestamp_remaining % TICKS_PER_DAY as i64) as u32;
-            let minute = (day_ticks / TICKS_PER_MINUTE as u32) as u16;
-            let tick = (day_ticks % TICKS_PER_MINUTE as u32) as u8;
-
-            Self {
-                date: MetrumDate { year, day },
-                time: MetrumTime { minute, tick, subtick: 0 }
-            }
-        } else {
-            year -= 1;
-            let day_count = if is_leap_year(year) {366} else {365};
-
-            let mut day = (timestamp_remaining / TICKS_PER_DAY as i64 + day_count) as u16;
-            let mut day_ticks = (timestamp_remaining % TICKS_PER_DAY as i64 + TICKS_PER_DAY as i64) as u32;
-            if day_ticks != TICKS_PER_DAY {
-                day -= 1;
-            } else {
-                day_ticks = 0;
-            }
-            let minute = (day_ticks / TICKS_PER_MINUTE as u32) as u16;
-            let tick = (day_ticks % TICKS_PER_MINUTE as u32) as u8;
+        let total_days = timestamp.div_euclid(TICKS_PER_DAY as i64);
+        let day_ticks = timestamp.rem_euclid(TICKS_PER_DAY as i64) as u32;
 
-            Self {
-                date: MetrumDate { year, day },
-                time: MetrumTime { minute, tick, subtick: 0 }
-            }
+        let (year, day) = year_and_day_from_epoch_days(total_days);
+        let minute = (day_ticks / TICKS_PER_MINUTE as u32) as u16;
+        let tick = (day_ticks % TICKS_PER_MINUTE as u32) as u8;
+
+        Self {
+            date: MetrumDate { year, day },
+            time: MetrumTime { minute, tick, subtick: 0 }
         }
     }
 
@@ -188,14 +198,71 @@ impl MetrumDateTime {
         })
     }
 
+    /// Builds a datetime from an externally supplied Unix-epoch clock reading (seconds and
+    /// nanoseconds since 1970-01-01 UTC), without going through chrono. This is the no_std path:
+    /// embedded or WASM callers can feed their own clock here instead of using [`Self::now`].
+    pub fn from_unix_timestamp(seconds: i64, nanos: u32) -> Self {
+        const UNIX_TO_METRUM_EPOCH_DAYS: i64 = 10_957; // 1970-01-01 -> 2000-01-01
+
+        let day_seconds = seconds.rem_euclid(86_400);
+        let total_unix_days = seconds.div_euclid(86_400);
+        let epoch_days = total_unix_days - UNIX_TO_METRUM_EPOCH_DAYS;
+
+        let day_micros = day_seconds as u64 * 1_000_000 + (nanos as u64 / 1_000);
+        let day_ticks = (day_micros / MICROS_PER_TICK as u64) as u32;
+        let subtick = (day_micros % MICROS_PER_TICK as u64) as u32;
+
+        let (year, day) = year_and_day_from_epoch_days(epoch_days);
+        let minute = (day_ticks / TICKS_PER_MINUTE as u32) as u16;
+        let tick = (day_ticks % TICKS_PER_MINUTE as u32) as u8;
+
+        Self {
+            date: MetrumDate { year, day },
+            time: MetrumTime { minute, tick, subtick }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
     pub fn from_naive(naive: NaiveDateTime) -> Self {
         Self::from_utc(naive.year(), naive.month() as u8, naive.day() as u8, naive.hour() as u8, naive.minute() as u8, naive.second() as u8, naive.nanosecond()).unwrap()
     }
 
+    #[cfg(feature = "chrono")]
     pub fn now() -> Self {
         Self::from_naive(Utc::now().naive_utc())
     }
 
+    /// Builds a datetime from a UTC clock reading shifted by a fixed offset, e.g. for displaying
+    /// Metrum time in a viewer's own zone rather than UTC. `offset_seconds` is east-of-UTC, same
+    /// sign convention as `chrono`'s `FixedOffset`.
+    #[cfg(feature = "chrono")]
+    pub fn from_offset(naive_utc: NaiveDateTime, offset_seconds: i32) -> Self {
+        Self::from_naive(naive_utc).with_offset(offset_seconds)
+    }
+
+    /// Like [`Self::now`], but in the system's local timezone instead of UTC.
+    #[cfg(feature = "chrono")]
+    pub fn now_local() -> Self {
+        let local = Local::now();
+        Self::from_offset(local.naive_utc(), local.offset().fix().local_minus_utc())
+    }
+
+    /// Shifts this datetime by a fixed number of seconds, rolling over minutes/ticks/days/years
+    /// as needed. `offset_seconds` is east-of-UTC, same sign convention as `chrono`'s
+    /// `FixedOffset`. Pure `core` arithmetic, so it works without the `chrono` feature.
+    pub fn with_offset(&self, offset_seconds: i32) -> Self {
+        let total_micros = self.timestamp() as i128 * MICROS_PER_TICK as i128
+            + self.subtick() as i128
+            + offset_seconds as i128 * 1_000_000;
+
+        let ticks = total_micros.div_euclid(MICROS_PER_TICK as i128) as i64;
+        let subtick = total_micros.rem_euclid(MICROS_PER_TICK as i128) as u32;
+
+        let mut shifted = Self::from_timestamp(ticks);
+        shifted.set_subtick(subtick);
+        shifted
+    }
+
     pub fn year(&self) -> i32 {self.date.year}
     pub fn day(&self) -> u16 {self.date.day}
     pub fn minute(&self) -> u16 {self.time.minute}
@@ -205,60 +272,100 @@ impl MetrumDateTime {
     pub fn set_subtick(&mut self, subtick: u32) { self.time.subtick = subtick; }
 
     pub fn timestamp(&self) -> i64 {
-        const YEAR_OFFSET: i32 = 2000;
-        let offset_year = self.year() - YEAR_OFFSET;
-        let mut timestamp_ticks = 0;
-
-        if offset_year >= 0 {
-            for current_year in 0..offset_year {
-                let days_in_year = if is_leap_year(current_year + YEAR_OFFSET) { 366 } else { 365 };
-                timestamp_ticks += days_in_year * 100_000;
-            }
-        } else if offset_year < 0 {
-            for current_year in offset_year..0 {
-                let days_in_year = if is_leap_year(current_year + YEAR_OFFSET) { 366 } else { 365 };
-                timestamp_ticks -= days_in_year * 100_000;
-            }
-        }
-        timestamp_ticks += self.day() as i64 * 100_000 + self.minute() as i64 * 100 + self.tick() as i64;
-
-        timestamp_ticks 
+        (days_before_year(self.year()) + self.day() as i64) * TICKS_PER_DAY as i64
+            + self.minute() as i64 * TICKS_PER_MINUTE as i64
+            + self.tick() as i64
     }
 }
 
 
-impl std::fmt::Display for MetrumDateTime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MetrumDateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} {}", self.date, self.time)
     }
 }
+impl core::str::FromStr for MetrumDateTime {
+    type Err = TimeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, time) = s.split_once(' ').ok_or(TimeError::ParseMismatch)?;
+        Ok(Self {
+            date: date.parse()?,
+            time: time.parse()?
+        })
+    }
+}
 
 
-pub fn year_day(year: i32, month: chrono::Month, day: u8) -> u16 {
+/// Number of days in `year` (365, or 366 in a leap year).
+pub fn days_in_year(year: i32) -> u16 {
+    if is_leap_year(year) { DAYS_PER_LEAP_YEAR } else { DAYS_PER_COMMON_YEAR }
+}
+
+/// Day-of-year (0-based) for `month` (1-based, 1..=12) / `day` (1-based) in `year`. `month` is
+/// assumed already validated by the caller.
+pub fn year_day(year: i32, month: u8, day: u8) -> u16 {
     let mut year_day = day as u16 - 1; // day in the year starting at 0
 
-    for previous_month in 1..month.number_from_month() {
-        let month = chrono::Month::from_u32(previous_month).unwrap();
-        year_day += days_in_month(month, year) as u16;
-        
+    for previous_month in 1..month {
+        year_day += days_in_month(previous_month, year) as u16;
     }
     year_day
 }
 
-fn days_in_month(month: chrono::Month, year: i32) -> u8 {
-    use chrono::Month::*;
+/// Inverse of `year_day`: recovers the 1-based `(month, day)` for a 0-based day-of-year in
+/// `year`. `year_day` is assumed already validated by the caller (< `days_in_year(year)`).
+fn month_day_from_year_day(year: i32, year_day: u16) -> (u8, u8) {
+    let mut remaining = year_day;
+    let mut month: u8 = 1;
+    while remaining >= days_in_month(month, year) as u16 {
+        remaining -= days_in_month(month, year) as u16;
+        month += 1;
+    }
+    (month, remaining as u8 + 1)
+}
+
+/// Days in `month` (1-based, 1..=12) for `year`. `month` is assumed already validated by the
+/// caller.
+fn days_in_month(month: u8, year: i32) -> u8 {
     match month {
-        January | March | May | July | August | October | December => 31,
-        April | June | September | November => 30,
-        February => {
-            if is_leap_year(year) {
-                29
-            } else {
-                28
-            }
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        _ => unreachable!("month out of range")
+    }
+}
+
+/// Number of leap years in `1..=y`. Uses floor division (`div_euclid`) rather than `/`, since `y`
+/// can be zero or negative here (`days_before_year` calls this with `year - 1`) and Rust's `/`
+/// truncates toward zero instead of flooring, which undercounts leap years for negative `y`.
+fn leaps(y: i64) -> i64 {
+    y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400)
+}
+
+/// Days from 2000-01-01 to the start of `year`, closed-form (no per-year looping).
+fn days_before_year(year: i32) -> i64 {
+    let year = year as i64;
+    365 * (year - YEAR_OFFSET as i64) + (leaps(year - 1) - leaps(YEAR_OFFSET as i64 - 1))
+}
+
+/// Inverse of `days_before_year`: recovers `(year, day_of_year)` from a day count relative to
+/// 2000-01-01, using Howard Hinnant's civil-from-days approach (estimate, then nudge) so it stays
+/// closed-form instead of looping one year at a time.
+fn year_and_day_from_epoch_days(total_days: i64) -> (i32, u16) {
+    // 146_097 = days in a 400-year Gregorian cycle; this integer ratio approximates the year
+    // without floating point, which core alone (no_std) doesn't provide `floor` for.
+    let mut year = YEAR_OFFSET + (total_days * 400).div_euclid(146_097) as i32;
+    loop {
+        if total_days < days_before_year(year) {
+            year -= 1;
+        } else if total_days >= days_before_year(year + 1) {
+            year += 1;
+        } else {
+            break;
         }
-        
     }
+    let day = (total_days - days_before_year(year)) as u16;
+    (year, day)
 }
 
 fn is_leap_year(year: i32) -> bool {
@@ -275,15 +382,16 @@ fn is_leap_year(year: i32) -> bool {
 
 #[test]
 fn leap_years() {
-    assert!(days_in_month(chrono::Month::February, 1980) == 29);
-    assert!(days_in_month(chrono::Month::February, 2000) == 29);
-    assert!(days_in_month(chrono::Month::February, 2020) == 29);
+    assert!(days_in_month(2, 1980) == 29);
+    assert!(days_in_month(2, 2000) == 29);
+    assert!(days_in_month(2, 2020) == 29);
 
-    assert!(days_in_month(chrono::Month::February, 1900) == 28);
-    assert!(days_in_month(chrono::Month::February, 2014) == 28);
+    assert!(days_in_month(2, 1900) == 28);
+    assert!(days_in_month(2, 2014) == 28);
 }
 
 #[test]
+#[cfg(feature = "chrono")]
 fn constructors() {
     use chrono::TimeZone;
     let mut date_time = MetrumDateTime::from_naive(Utc.ymd(1970, 12, 24).naive_utc().and_time(chrono::NaiveTime::from_hms(12, 15, 17)));
@@ -296,6 +404,7 @@ fn constructors() {
 }
 
 #[test]
+#[cfg(feature = "chrono")]
 fn timestamps() {
     let mut now = MetrumDateTime::now();
     now.set_subtick(0);
@@ -305,4 +414,52 @@ fn timestamps() {
     let mut moon_landing = MetrumDateTime::from_utc(1969, 7, 20, 20, 17, 40, 0).unwrap();
     moon_landing.set_subtick(0);
     assert_eq!(moon_landing, MetrumDateTime::from_timestamp(moon_landing.timestamp()));
+}
+
+#[test]
+fn from_unix_timestamp_matches_from_utc() {
+    let mut moon_landing = MetrumDateTime::from_utc(1969, 7, 20, 20, 17, 40, 0).unwrap();
+    moon_landing.set_subtick(0);
+
+    // 1969-07-20T20:17:40Z
+    let mut from_unix = MetrumDateTime::from_unix_timestamp(-14_182_940, 0);
+    from_unix.set_subtick(0);
+    assert_eq!(from_unix, moon_landing);
+}
+
+#[test]
+fn with_offset_rolls_over_day_boundary() {
+    let mut last_tick_of_year = MetrumDateTime::new(2023, days_in_year(2023) - 1, MINUTES_PER_DAY - 1, TICKS_PER_MINUTE - 1, 0).unwrap();
+    last_tick_of_year.set_subtick(0);
+
+    let shifted = last_tick_of_year.with_offset(1);
+    assert_eq!(shifted.year(), 2024);
+    assert_eq!(shifted.day(), 0);
+    assert_eq!(shifted.minute(), 0);
+    assert_eq!(shifted.tick(), 0);
+
+    let back = shifted.with_offset(-1);
+    assert_eq!(back, last_tick_of_year);
+}
+
+#[test]
+fn timestamp_is_monotonic_across_year_zero() {
+    // Year 0 is a leap year, so 0000-12-31 is one day before 0001-01-01 - `leaps` used truncating
+    // division here for a while, which undercounted leap years for `y <= 0` and made these two
+    // instants collapse onto the same timestamp instead of `TICKS_PER_DAY` ticks apart.
+    let end_of_year_0 = MetrumDateTime::from_utc(0, 12, 31, 0, 0, 0, 0).unwrap();
+    let start_of_year_1 = MetrumDateTime::from_utc(1, 1, 1, 0, 0, 0, 0).unwrap();
+
+    assert_eq!(start_of_year_1.timestamp() - end_of_year_0.timestamp(), TICKS_PER_DAY as i64);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn display_from_str_round_trip() {
+    let date_time = MetrumDateTime::new(2024, 123, 456, 78, 42).unwrap();
+    let parsed: MetrumDateTime = date_time.to_string().parse().unwrap();
+    assert_eq!(date_time, parsed);
+
+    assert!("2024'999 456:78.000000".parse::<MetrumDateTime>().is_err());
+    assert!("not-a-date-time".parse::<MetrumDateTime>().is_err());
 }
\ No newline at end of file
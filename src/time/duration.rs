@@ -0,0 +1,185 @@
+use core::ops::{Add, Sub};
+
+use super::{MetrumDateTime, MICROS_PER_TICK, TICKS_PER_DAY};
+
+/// A signed span of Metrum time, stored as whole ticks plus a subtick (microsecond) fraction,
+/// mirroring how `chrono::Duration` splits into seconds and nanos. `dt2 - dt1` produces one of
+/// these, and `dt + duration` advances a [`MetrumDateTime`] by it.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MetrumDuration {
+    ticks: i64,
+    subticks: u32
+}
+
+impl MetrumDuration {
+    fn from_total_subticks(total_subticks: i128) -> Self {
+        let per_tick = MICROS_PER_TICK as i128;
+        Self {
+            ticks: total_subticks.div_euclid(per_tick) as i64,
+            subticks: total_subticks.rem_euclid(per_tick) as u32
+        }
+    }
+
+    fn total_subticks(&self) -> i128 {
+        self.ticks as i128 * MICROS_PER_TICK as i128 + self.subticks as i128
+    }
+
+    pub fn ticks(&self) -> i64 { self.ticks }
+    pub fn subticks(&self) -> u32 { self.subticks }
+
+    /// Decomposes this duration into whole years, remaining days, minutes, ticks and subticks, in
+    /// the spirit of pendulum's `precise_diff`. `start` anchors the decomposition (`start + self`
+    /// is the other endpoint), so years are counted as real calendar years between the two
+    /// endpoints - shifting month/day forward by whole years and clamping a Feb 29 start down
+    /// to Feb 28 in a non-leap target year - rather than assuming every year is as long as
+    /// whichever Metrum-epoch year the flat tick count happens to land on. The year count is
+    /// estimated from the exact 400-year Gregorian cycle length and then nudged to the real value
+    /// (same trick as `year_and_day_from_epoch_days`), so this stays O(1) instead of looping once
+    /// per year even when `start`/`end` are arbitrary, far-apart `i64` tick counts.
+    pub fn breakdown(&self, start: &MetrumDateTime) -> DurationBreakdown {
+        let negative = self.total_subticks() < 0;
+        let end = start.clone() + *self;
+        let (from, to) = if negative { (end, start.clone()) } else { (start.clone(), end) };
+
+        // 146_097 days every 400 years, exactly, by the Gregorian leap rule - so this ratio never
+        // drifts, unlike an average based on 365.2425 days/year.
+        let avg_year_subticks = 146_097i128 * TICKS_PER_DAY as i128 * MICROS_PER_TICK as i128 / 400;
+        let span = total_subticks_of(&to) - total_subticks_of(&from);
+        let mut years = (span / avg_year_subticks).max(0) as i32;
+
+        while total_subticks_of(&add_calendar_years(&from, years + 1)) <= total_subticks_of(&to) {
+            years += 1;
+        }
+        while total_subticks_of(&add_calendar_years(&from, years)) > total_subticks_of(&to) {
+            years -= 1;
+        }
+
+        let anchor = add_calendar_years(&from, years);
+        let remaining = to - anchor;
+
+        let as_date_time = MetrumDateTime::from_timestamp(remaining.ticks());
+        DurationBreakdown {
+            negative,
+            years,
+            days: as_date_time.day(),
+            minutes: as_date_time.minute(),
+            ticks: as_date_time.tick(),
+            subticks: remaining.subticks()
+        }
+    }
+}
+
+/// Shifts `from`'s calendar month/day `years` years forward (e.g. 2024-03-01 + 1 year =
+/// 2025-03-01), clamping Feb 29 down to Feb 28 when the target year isn't a leap year.
+fn add_calendar_years(from: &MetrumDateTime, years: i32) -> MetrumDateTime {
+    let (month, day) = super::month_day_from_year_day(from.year(), from.day());
+    let target_year = from.year() + years;
+    let day = day.min(super::days_in_month(month, target_year));
+    let day_of_year = super::year_day(target_year, month, day);
+    MetrumDateTime::new(target_year, day_of_year, from.minute(), from.tick(), from.subtick()).unwrap()
+}
+
+/// The leap-year-aware decomposition produced by [`MetrumDuration::breakdown`].
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct DurationBreakdown {
+    pub negative: bool,
+    pub years: i32,
+    pub days: u16,
+    pub minutes: u16,
+    pub ticks: u8,
+    pub subticks: u32
+}
+
+fn total_subticks_of(date_time: &MetrumDateTime) -> i128 {
+    date_time.timestamp() as i128 * MICROS_PER_TICK as i128 + date_time.subtick() as i128
+}
+
+impl Sub for MetrumDateTime {
+    type Output = MetrumDuration;
+    fn sub(self, rhs: Self) -> MetrumDuration {
+        MetrumDuration::from_total_subticks(total_subticks_of(&self) - total_subticks_of(&rhs))
+    }
+}
+
+impl Add<MetrumDuration> for MetrumDateTime {
+    type Output = MetrumDateTime;
+    fn add(self, rhs: MetrumDuration) -> MetrumDateTime {
+        let per_tick = MICROS_PER_TICK as i128;
+        let total = total_subticks_of(&self) + rhs.total_subticks();
+
+        let mut result = MetrumDateTime::from_timestamp(total.div_euclid(per_tick) as i64);
+        result.set_subtick(total.rem_euclid(per_tick) as u32);
+        result
+    }
+}
+
+#[test]
+fn sub_then_add_round_trips() {
+    let start = MetrumDateTime::new(2024, 100, 500, 50, 123_456).unwrap();
+    let end = MetrumDateTime::new(2025, 10, 200, 10, 654_321).unwrap();
+
+    let duration = end.clone() - start.clone();
+    assert_eq!(start + duration, end);
+}
+
+#[test]
+fn breakdown_accounts_for_leap_years() {
+    let start = MetrumDateTime::new(2000, 0, 0, 0, 0).unwrap();
+    let end = MetrumDateTime::new(2004, 0, 0, 0, 0).unwrap();
+    let breakdown = (end - start.clone()).breakdown(&start);
+
+    assert!(!breakdown.negative);
+    assert_eq!(breakdown.years, 4);
+    assert_eq!(breakdown.days, 0);
+}
+
+#[test]
+fn negative_duration_breaks_down_correctly() {
+    let start = MetrumDateTime::new(2024, 100, 0, 0, 0).unwrap();
+    let end = MetrumDateTime::new(2023, 50, 0, 0, 0).unwrap();
+
+    let breakdown = (end - start.clone()).breakdown(&start);
+    assert!(breakdown.negative);
+}
+
+#[test]
+fn breakdown_anchors_on_the_real_endpoints_not_the_epoch() {
+    // Exactly one calendar year apart (no Feb 29 in between either direction), so this should
+    // report 1 whole year and 0 remaining days - not get distorted by 2000 (the Metrum epoch)
+    // being a leap year.
+    let start = MetrumDateTime::from_utc(2024, 3, 1, 0, 0, 0, 0).unwrap();
+    let end = MetrumDateTime::from_utc(2025, 3, 1, 0, 0, 0, 0).unwrap();
+
+    let breakdown = (end - start.clone()).breakdown(&start);
+    assert!(!breakdown.negative);
+    assert_eq!(breakdown.years, 1);
+    assert_eq!(breakdown.days, 0);
+    assert_eq!(breakdown.minutes, 0);
+}
+
+#[test]
+fn breakdown_clamps_a_leap_day_anniversary() {
+    // 2024-02-29 -> 2025-02-29 doesn't exist, so the 1-year anniversary clamps to 2025-02-28,
+    // leaving exactly 1 remaining day.
+    let start = MetrumDateTime::from_utc(2024, 2, 29, 0, 0, 0, 0).unwrap();
+    let end = MetrumDateTime::from_utc(2025, 3, 1, 0, 0, 0, 0).unwrap();
+
+    let breakdown = (end - start.clone()).breakdown(&start);
+    assert!(!breakdown.negative);
+    assert_eq!(breakdown.years, 1);
+    assert_eq!(breakdown.days, 1);
+}
+
+#[test]
+fn sub_then_add_round_trips_with_real_clock_subticks() {
+    // `from_utc`'s subtick is a microsecond value (mod MICROS_PER_TICK), unlike the hand-picked
+    // constants above; exercise that scale through a full Sub/Add round trip.
+    let half_tick_nanos = 432_000_000; // half of 864ms, i.e. half a tick
+    let start = MetrumDateTime::from_utc(2024, 1, 1, 0, 0, 0, 0).unwrap();
+    let end = MetrumDateTime::from_utc(2024, 1, 1, 0, 0, 0, half_tick_nanos).unwrap();
+
+    let duration = end.clone() - start.clone();
+    assert_eq!(duration.ticks(), 0);
+    assert_eq!(duration.subticks(), MICROS_PER_TICK / 2);
+    assert_eq!(start + duration, end);
+}
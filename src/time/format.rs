@@ -0,0 +1,145 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use super::{MetrumDateTime, TimeError};
+
+/// Error produced while rendering a [`MetrumDateTime`] with [`MetrumDateTime::format`].
+#[derive(Debug)]
+pub enum FmtError {
+    /// A `%` escape was followed by a character that isn't a known specifier.
+    UnknownSpecifier(char),
+    /// The pattern ended right after a `%`, with no specifier to read.
+    TrailingPercent
+}
+
+impl MetrumDateTime {
+    /// Renders this datetime using a chrono-strftime-style pattern.
+    ///
+    /// Supported specifiers: `%Y` year, `%j` day-of-year (3 digits), `%n` minute (3 digits),
+    /// `%k` tick (2 digits), `%u` subtick (6 digits), `%%` literal `%`. Any other character is
+    /// passed through literally; `%` followed by an unknown specifier, or a trailing `%`, is an
+    /// error.
+    pub fn format(&self, fmt: &str) -> Result<String, FmtError> {
+        let mut out = String::with_capacity(fmt.len());
+        let mut chars = fmt.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&self.year().to_string()),
+                Some('j') => out.push_str(&format!("{:0>3}", self.day())),
+                Some('n') => out.push_str(&format!("{:0>3}", self.minute())),
+                Some('k') => out.push_str(&format!("{:0>2}", self.tick())),
+                Some('u') => out.push_str(&format!("{:0>6}", self.subtick())),
+                Some('%') => out.push('%'),
+                Some(other) => return Err(FmtError::UnknownSpecifier(other)),
+                None => return Err(FmtError::TrailingPercent)
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Parses a [`MetrumDateTime`] out of `s` by walking `fmt` in lockstep, mirroring
+/// [`MetrumDateTime::format`]. Literal characters in `fmt` must match `s` exactly; `%Y` reads
+/// a signed, variable-width integer, while `%j`/`%n`/`%k`/`%u` read fixed-width digit runs
+/// (3, 3, 2 and 6 digits respectively). Any mismatch or out-of-range component is reported as
+/// `TimeError::ParseMismatch` or the corresponding validation error from `MetrumDateTime::new`.
+pub fn parse_from_str(s: &str, fmt: &str) -> Result<MetrumDateTime, TimeError> {
+    let mut year = None;
+    let mut day = None;
+    let mut minute = None;
+    let mut tick = None;
+    let mut subtick = None;
+
+    let mut s_chars = s.chars().peekable();
+    let mut fmt_chars = fmt.chars();
+
+    while let Some(fc) = fmt_chars.next() {
+        if fc != '%' {
+            match s_chars.next() {
+                Some(sc) if sc == fc => continue,
+                _ => return Err(TimeError::ParseMismatch)
+            }
+        }
+
+        match fmt_chars.next() {
+            Some('Y') => year = Some(read_int(&mut s_chars)?),
+            Some('j') => day = Some(read_fixed_uint(&mut s_chars, 3)? as u16),
+            Some('n') => minute = Some(read_fixed_uint(&mut s_chars, 3)? as u16),
+            Some('k') => tick = Some(read_fixed_uint(&mut s_chars, 2)? as u8),
+            Some('u') => subtick = Some(read_fixed_uint(&mut s_chars, 6)?),
+            Some('%') if s_chars.next() == Some('%') => {},
+            _ => return Err(TimeError::ParseMismatch)
+        }
+    }
+
+    if s_chars.next().is_some() {
+        return Err(TimeError::ParseMismatch);
+    }
+
+    MetrumDateTime::new(
+        year.ok_or(TimeError::ParseMismatch)?,
+        day.ok_or(TimeError::ParseMismatch)?,
+        minute.ok_or(TimeError::ParseMismatch)?,
+        tick.ok_or(TimeError::ParseMismatch)?,
+        subtick.unwrap_or(0)
+    )
+}
+
+fn read_fixed_uint(chars: &mut Peekable<Chars>, width: usize) -> Result<u32, TimeError> {
+    let mut buf = String::with_capacity(width);
+    for _ in 0..width {
+        match chars.next() {
+            Some(c) if c.is_ascii_digit() => buf.push(c),
+            _ => return Err(TimeError::ParseMismatch)
+        }
+    }
+    buf.parse().map_err(|_| TimeError::ParseMismatch)
+}
+
+fn read_int(chars: &mut Peekable<Chars>) -> Result<i32, TimeError> {
+    let mut buf = String::new();
+    if chars.peek() == Some(&'-') {
+        buf.push('-');
+        chars.next();
+    }
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        buf.push(c);
+        chars.next();
+    }
+    if buf.is_empty() || buf == "-" {
+        return Err(TimeError::ParseMismatch);
+    }
+    buf.parse().map_err(|_| TimeError::ParseMismatch)
+}
+
+#[test]
+fn format_specifiers() {
+    let dt = MetrumDateTime::new(2024, 123, 456, 78, 0).unwrap();
+    assert_eq!(dt.format("%Y'%j %n:%k.%u").unwrap(), "2024'123 456:78.000000");
+    assert_eq!(dt.format("100%%").unwrap(), "100%");
+    assert!(matches!(dt.format("%q"), Err(FmtError::UnknownSpecifier('q'))));
+    assert!(matches!(dt.format("abc%"), Err(FmtError::TrailingPercent)));
+}
+
+#[test]
+fn format_parse_round_trip() {
+    let dt = MetrumDateTime::new(2024, 123, 456, 78, 42).unwrap();
+    let fmt = "%Y'%j %n:%k.%u";
+    let rendered = dt.format(fmt).unwrap();
+    assert_eq!(parse_from_str(&rendered, fmt).unwrap(), dt);
+}
+
+#[test]
+fn parse_rejects_mismatches() {
+    assert!(parse_from_str("2024'999 456:78.000000", "%Y'%j %n:%k.%u").is_err());
+    assert!(parse_from_str("2024-123 456:78.000000", "%Y'%j %n:%k.%u").is_err());
+}
@@ -0,0 +1,63 @@
+//! Alternate `serde` representations for [`MetrumDateTime`], selected with `#[serde(with = "...")]`
+//! on a field of the user's own struct, mirroring how `chrono::serde` offers `ts_seconds` alongside
+//! the default `DateTime` impl.
+
+use ::serde::de::Error as _;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::MetrumDateTime;
+
+/// Serializes a [`MetrumDateTime`] as the raw `i64` tick count from [`MetrumDateTime::timestamp`].
+pub mod ts_ticks {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(date_time: &MetrumDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        date_time.timestamp().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MetrumDateTime, D::Error> {
+        let ticks = i64::deserialize(deserializer)?;
+        Ok(MetrumDateTime::from_timestamp(ticks))
+    }
+}
+
+/// Serializes a [`MetrumDateTime`] as its `Display` string, parsed back via `FromStr`.
+pub mod string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(date_time: &MetrumDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        date_time.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MetrumDateTime, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|err| D::Error::custom(format!("invalid MetrumDateTime: {:?}", err)))
+    }
+}
+
+#[test]
+fn ts_ticks_round_trips_through_json() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Wrapper(#[serde(with = "ts_ticks")] MetrumDateTime);
+
+    // ts_ticks only carries `timestamp()`'s whole-tick count, so use a subtick-less datetime -
+    // the `string` representation below covers the subtick-preserving case.
+    let dt = MetrumDateTime::new(2024, 123, 456, 78, 0).unwrap();
+    let json = serde_json::to_string(&Wrapper(dt.clone())).unwrap();
+
+    assert_eq!(json, dt.timestamp().to_string());
+    assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap().0, dt);
+}
+
+#[test]
+fn string_round_trips_through_json() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Wrapper(#[serde(with = "string")] MetrumDateTime);
+
+    let dt = MetrumDateTime::new(2024, 123, 456, 78, 42).unwrap();
+    let json = serde_json::to_string(&Wrapper(dt.clone())).unwrap();
+
+    assert_eq!(json, format!("{:?}", dt.to_string()));
+    assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap().0, dt);
+}
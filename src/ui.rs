@@ -1,10 +1,58 @@
 use std::io;
 
-use tui::{Frame, Terminal, backend::{Backend, TermionBackend}, layout::{Constraint, Direction, Layout}, style::{Color, Style}, text::{Span, Spans}, widgets::{Gauge, Paragraph, Wrap}};
+use tui::{Frame, Terminal, backend::{Backend, TermionBackend}, layout::{Constraint, Direction, Layout}, style::Style, text::{Span, Spans}, widgets::{Gauge, Paragraph, Wrap}};
 use tui::widgets::{Block, Borders};
 use termion::raw::{IntoRawMode, RawTerminal};
 
-use crate::time::{self, MetrumDateTime};
+use metrum::time::{self, MetrumDateTime, TimeError};
+
+mod config;
+use config::Theme;
+
+/// Where [`Cli::new`] looks for user theming, relative to the working directory it's launched
+/// from.
+const THEME_PATH: &str = "metrum.toml";
+
+/// Which clock a [`Cli`] renders: plain UTC, the system's local timezone, or a fixed offset in
+/// seconds east of UTC. Cycled with the `z` key.
+#[derive(Clone, Copy, PartialEq)]
+enum TimeZoneMode {
+    Utc,
+    Local,
+    Fixed(i32)
+}
+
+/// Fixed offsets `z` cycles through after `Local`, before wrapping back to `Utc`.
+const FIXED_OFFSETS: [i32; 3] = [5 * 3600, -5 * 3600, 9 * 3600];
+
+impl TimeZoneMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Utc => Self::Local,
+            Self::Local => Self::Fixed(FIXED_OFFSETS[0]),
+            Self::Fixed(offset) => match FIXED_OFFSETS.iter().position(|&o| o == offset) {
+                Some(i) if i + 1 < FIXED_OFFSETS.len() => Self::Fixed(FIXED_OFFSETS[i + 1]),
+                _ => Self::Utc
+            }
+        }
+    }
+
+    fn now(self) -> MetrumDateTime {
+        match self {
+            Self::Utc => MetrumDateTime::now(),
+            Self::Local => MetrumDateTime::now_local(),
+            Self::Fixed(offset) => MetrumDateTime::now().with_offset(offset)
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Self::Utc => "UTC".to_string(),
+            Self::Local => "local".to_string(),
+            Self::Fixed(offset) => format!("UTC{:+03}:{:02}", offset / 3600, (offset.abs() / 60) % 60)
+        }
+    }
+}
 
 pub struct Cli<T: Backend> {
     pub terminal: Terminal<T>,
@@ -15,19 +63,29 @@ struct Settings {
     year_style: Style,
     day_style: Style,
     minute_style: Style,
-    tick_style: Style
+    tick_style: Style,
+    time_zone: TimeZoneMode,
+    /// `Some` freezes the display on that instant instead of following the live clock.
+    reference: Option<MetrumDateTime>,
+    /// `Some` while the "set time" input line is open, holding what's been typed so far.
+    input: Option<String>
 }
 
 impl Cli<TermionBackend<RawTerminal<io::Stdout>>> {
+    /// Colors are loaded from [`THEME_PATH`] if present, falling back to the built-in defaults.
     pub fn new() -> Self {
+        let theme = Theme::load(THEME_PATH).unwrap_or_default();
         let terminal = Terminal::new(TermionBackend::new(io::stdout().into_raw_mode().unwrap())).unwrap();
-        Cli { 
+        Cli {
             terminal,
             settings: Settings {
-                year_style: Style::default().fg(Color::Blue),
-                day_style: Style::default().fg(Color::LightBlue),
-                minute_style: Style::default().fg(Color::Red),
-                tick_style: Style::default().fg(Color::Yellow),
+                year_style: Style::default().fg(theme.year_color),
+                day_style: Style::default().fg(theme.day_color),
+                minute_style: Style::default().fg(theme.minute_color),
+                tick_style: Style::default().fg(theme.tick_color),
+                time_zone: TimeZoneMode::Utc,
+                reference: None,
+                input: None
             }
         }
     }
@@ -38,8 +96,54 @@ impl<T : Backend> Cli<T> {
         self.terminal.draw(|f| { Self::draw(settings, f); }).unwrap();
     }
 
+    /// Cycles the displayed clock between UTC, the local timezone, and a few fixed offsets.
+    pub fn cycle_timezone(&mut self) {
+        self.settings.time_zone = self.settings.time_zone.next();
+    }
+
+    /// Opens the "set time" input line. Feed keystrokes to it with [`Self::push_input_char`] /
+    /// [`Self::backspace_input`], then [`Self::confirm_input`] or [`Self::cancel_input`].
+    pub fn begin_input(&mut self) {
+        self.settings.input = Some(String::new());
+    }
+
+    pub fn push_input_char(&mut self, c: char) {
+        if let Some(input) = &mut self.settings.input {
+            input.push(c);
+        }
+    }
+
+    pub fn backspace_input(&mut self) {
+        if let Some(input) = &mut self.settings.input {
+            input.pop();
+        }
+    }
+
+    pub fn cancel_input(&mut self) {
+        self.settings.input = None;
+    }
+
+    pub fn is_input_active(&self) -> bool {
+        self.settings.input.is_some()
+    }
+
+    /// Parses the input line as a [`MetrumDateTime`] and freezes the display on it. On a parse
+    /// error the input line is left open so the user can correct it.
+    pub fn confirm_input(&mut self) -> Result<(), TimeError> {
+        let input = self.settings.input.clone().unwrap_or_default();
+        let parsed: MetrumDateTime = input.parse()?;
+        self.settings.reference = Some(parsed);
+        self.settings.input = None;
+        Ok(())
+    }
+
+    /// Unfreezes the display, returning to the live clock.
+    pub fn resume(&mut self) {
+        self.settings.reference = None;
+    }
+
     fn draw(settings: &Settings, f: &mut Frame<T>) {
-        let now = MetrumDateTime::now();
+        let now = settings.reference.clone().unwrap_or_else(|| settings.time_zone.now());
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
@@ -51,14 +155,24 @@ impl<T : Backend> Cli<T> {
                 ].as_ref()
             )
             .split(f.size());
-    
-        let time_paragraph = Paragraph::new(time_text(settings, &now))
-            .block(
-                Block::default()
-                    .title("Metrum time")
-                    .borders(Borders::ALL)
-            ).wrap(Wrap { trim: true });
-        
+
+        let time_paragraph = if let Some(input) = &settings.input {
+            Paragraph::new(Spans::from(vec![Span::raw(input.as_str())]))
+                .block(
+                    Block::default()
+                        .title("Set time (Enter to confirm, Esc to cancel)")
+                        .borders(Borders::ALL)
+                )
+        } else {
+            let status = if settings.reference.is_some() { ", paused" } else { "" };
+            Paragraph::new(time_text(settings, &now))
+                .block(
+                    Block::default()
+                        .title(format!("Metrum time ({}{})", settings.time_zone.label(), status))
+                        .borders(Borders::ALL)
+                ).wrap(Wrap { trim: true })
+        };
+
         f.render_widget(time_paragraph, chunks[0]);
         let gauges_block = Block::default()
             .title("Progress")
@@ -106,6 +220,7 @@ fn time_text<'a>(settings: &Settings, time: &MetrumDateTime) -> Spans<'a> {
         Span::styled(format!("{:03}", time.minute()), settings.minute_style),
         Span::from(":"),
         Span::styled(format!("{:02}", time.tick()), settings.tick_style),
-        
+        Span::from("."),
+        Span::styled(format!("{:06}", time.subtick()), settings.tick_style),
     ])
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+//! Loads the four `ui::Cli` component colors from a user-supplied TOML file, so the theme doesn't
+//! have to be recompiled to change. Any key left out of the file (or the file missing entirely)
+//! falls back to the built-in [`Theme::default`] colors.
+
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+use tui::style::Color;
+
+#[derive(Deserialize)]
+struct RawTheme {
+    year_color: Option<String>,
+    day_color: Option<String>,
+    minute_color: Option<String>,
+    tick_color: Option<String>
+}
+
+/// Colors for the year/day/minute/tick components of the "Metrum time" display.
+pub(super) struct Theme {
+    pub year_color: Color,
+    pub day_color: Color,
+    pub minute_color: Color,
+    pub tick_color: Color
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            year_color: Color::Blue,
+            day_color: Color::LightBlue,
+            minute_color: Color::Red,
+            tick_color: Color::Yellow
+        }
+    }
+}
+
+impl Theme {
+    /// Reads and parses a theme from `path`, returning `None` if the file is missing or malformed
+    /// (the caller falls back to [`Theme::default`]). Keys that are present but don't name a known
+    /// color are also ignored rather than rejected, so a typo loses a custom color instead of the
+    /// whole file.
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let text = fs::read_to_string(path).ok()?;
+        let raw: RawTheme = toml::from_str(&text).ok()?;
+        let default = Self::default();
+
+        Some(Self {
+            year_color: raw.year_color.and_then(|c| parse_color(&c)).unwrap_or(default.year_color),
+            day_color: raw.day_color.and_then(|c| parse_color(&c)).unwrap_or(default.day_color),
+            minute_color: raw.minute_color.and_then(|c| parse_color(&c)).unwrap_or(default.minute_color),
+            tick_color: raw.tick_color.and_then(|c| parse_color(&c)).unwrap_or(default.tick_color)
+        })
+    }
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "gray" | "grey" => Some(Color::Gray),
+        "lightred" => Some(Color::LightRed),
+        "lightgreen" => Some(Color::LightGreen),
+        "lightyellow" => Some(Color::LightYellow),
+        "lightblue" => Some(Color::LightBlue),
+        "lightmagenta" => Some(Color::LightMagenta),
+        "lightcyan" => Some(Color::LightCyan),
+        "white" => Some(Color::White),
+        _ => None
+    }
+}